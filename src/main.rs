@@ -2,6 +2,7 @@ use {
     std::{
         collections::{
             BTreeSet,
+            HashMap,
             HashSet,
             btree_map::{
                 self,
@@ -10,17 +11,25 @@ use {
         },
         path::Path,
         str::FromStr,
+        sync::{
+            Arc,
+            LazyLock,
+            Mutex,
+        },
         time::Duration,
     },
     chrono::prelude::*,
-    if_chain::if_chain,
-    itertools::Itertools as _,
-    lazy_regex::regex_captures,
+    hmac::{
+        Hmac,
+        Mac as _,
+    },
+    regex::Regex,
+    rocket::data::ToByteUnit as _,
     serde::{
         Deserialize,
         Serialize,
     },
-    url::Url,
+    sha2::Sha256,
     wheel::{
         fs,
         traits::{
@@ -31,7 +40,7 @@ use {
     },
 };
 
-#[derive(Serialize)]
+#[derive(Clone, Default, Deserialize, Serialize)]
 struct DataPoint {
     day: String,
     open_issues: usize,
@@ -46,9 +55,11 @@ struct Report {
     last_updated: BTreeMap<u32, DateTime<Utc>>,
     #[serde(default)]
     issue_events_cache: BTreeMap<u32, Vec<IssueEvent>>,
-    #[serde(skip_deserializing)]
+    #[serde(default)]
     labels: BTreeSet<String>,
-    #[serde(skip_deserializing)]
+    #[serde(default)]
+    feed: Vec<FeedEntry>,
+    #[serde(default)]
     timeline: Vec<DataPoint>,
 }
 
@@ -74,15 +85,6 @@ impl FromStr for Repo {
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct Issue {
-    number: u32,
-    created_at: DateTime<Utc>,
-    updated_at: DateTime<Utc>,
-    pull_request: Option<serde_json::Value>,
-    events_url: Url,
-}
-
 #[derive(Deserialize, Serialize)]
 struct IssueEvent {
     created_at: DateTime<Utc>,
@@ -138,165 +140,281 @@ struct Label {
     name: String,
 }
 
+/// Path to the per-repo label canonicalization config, keyed by `org/repo`.
+const LABEL_CONFIG_PATH: &str = "config/labels.json";
+
+/// One label canonicalization rule as stored on disk: a regex `pattern` and the `canonical`
+/// replacement template it maps a matching label name to.
+#[derive(Deserialize)]
+struct LabelRule {
+    pattern: String,
+    canonical: String,
+}
+
+/// A [`LabelRule`] with its pattern compiled and anchored to match the whole label name.
+struct CompiledRule {
+    regex: Regex,
+    canonical: String,
+}
+
+/// Load and compile the canonicalization rules for one repository, returning an empty list (i.e.
+/// the identity mapping) when the repo has no config entry.
+async fn load_label_rules(org: &str, repo: &str) -> Result<Vec<CompiledRule>, Error> {
+    let config: BTreeMap<String, Vec<LabelRule>> = fs::read_json(LABEL_CONFIG_PATH).await.missing_ok()?;
+    config.get(&format!("{org}/{repo}"))
+        .into_iter()
+        .flatten()
+        .map(|LabelRule { pattern, canonical }| Ok(CompiledRule {
+            regex: Regex::new(&format!("^(?:{pattern})$"))?,
+            canonical: canonical.clone(),
+        }))
+        .collect()
+}
+
 impl Label {
+    /// Canonicalize this label by returning the `canonical` template of the first rule whose regex
+    /// matches the whole name (expanding any capture group references), falling back to the raw
+    /// name when no rule matches.
     #[must_use]
-    fn map(&self, org: &str, repo: &str) -> &str {
-        match (org, repo) {
-            ("OoTRandomizer", "OoT-Randomizer") => self.ootr_map(),
-            ("midoshouse", "ootr-multiworld") => self.mhmw_map(),
-            _ => &self.name,
+    fn map(&self, rules: &[CompiledRule]) -> String {
+        for CompiledRule { regex, canonical } in rules {
+            if let Some(captures) = regex.captures(&self.name) {
+                let mut mapped = String::new();
+                captures.expand(canonical, &mut mapped);
+                return mapped
+            }
         }
+        self.name.clone()
     }
+}
 
-    #[must_use]
-    fn ootr_map(&self) -> &str {
-        match &*self.name {
-            | "Changes Item Table"
-                => "Changes Item Table",
-            | "Algorithm Changes"
-            | "Component: Algorithm"
-                => "Component: Algorithm",
-            | "ASM/C Changes"
-            | "Component: ASM/C"
-                => "Component: ASM/C",
-            | "Component: Cosmetics"
-                => "Component: Cosmetics",
-            | "Component: Documentation"
-                => "Component: Documentation",
-            | "Component: GUI/Website"
-                => "Component: GUI/Website",
-            | "Component: Hints"
-                => "Component: Hints",
-            | "Component: Logic"
-            | "Logic Changes"
-                => "Component: Logic",
-            | "Component: Misc"
-                => "Component: Misc",
-            | "Component: Patching"
-                => "Component: Patching",
-            | "Component: Plandomizer"
-                => "Component: Plandomizer",
-            | "Component: Presets"
-                => "Component: Presets",
-            | "Component: Randomizer Core"
-                => "Component: Randomizer Core",
-            | "Component: Setting"
-                => "Component: Setting",
-            | "Component: Tricks/Glitches"
-                => "Component: Tricks/Glitches",
-            | "Racing Impact"
-                => "Racing Impact",
-            | "Status: Blocked"
-                => "Status: Blocked",
-            | "Status: Duplicate"
-            | "duplicate"
-                => "Status: Duplicate",
-            | "Status: Good First Issue"
-            | "good first issue"
-                => "Status: Good First Issue",
-            | "Status: Help Wanted"
-            | "help wanted"
-                => "Status: Help Wanted",
-            | "Needs Review"
-            | "Status: Needs Review"
-                => "Status: Needs Review",
-            | "Status: Needs Testing"
-                => "Status: Needs Testing",
-            | "Status: Under Consideration"
-                => "Status: Under Consideration",
-            | "Status: Waiting for Author"
-            | "Waiting for Author"
-            | "question"
-                => "Status: Waiting for Author",
-            | "Status: Waiting for Maintainers"
-                => "Status: Waiting for Maintainers",
-            | "Status: Waiting for Release"
-                => "Status: Waiting for Release",
-            | "Status: Won't Fix"
-            | "wontfix"
-                => "Status: Won't Fix",
-            | "Trivial"
-            | "trivial"
-                => "Trivial",
-            | "Type: Bug"
-            | "bug"
-                => "Type: Bug",
-            | "Type: Enhancement"
-            | "enhancement"
-                => "Type: Enhancement",
-            | "Type: Maintenance"
-                => "Type: Maintenance",
-            _ => &self.name,
+const GRAPHQL_ENDPOINT: &str = "https://api.github.com/graphql";
+
+/// How many timeline items to request per issue in a single round trip. Issues with more are
+/// paginated with follow-up queries.
+const TIMELINE_PAGE_SIZE: u8 = 100;
+
+/// The inlined selection for the `timelineItems` nodes, shared between the listing query and the
+/// per-issue pagination query.
+const TIMELINE_NODES: &str = "__typename ... on LabeledEvent { createdAt label { name } } ... on UnlabeledEvent { createdAt label { name } } ... on ClosedEvent { createdAt } ... on ReopenedEvent { createdAt }";
+
+/// An issue or pull request together with the timeline items fetched inline with it, as returned by
+/// the GraphQL API. `more_events` holds the cursor of the inner `timelineItems` connection when the
+/// issue has more than [`TIMELINE_PAGE_SIZE`] events.
+struct FetchedIssue {
+    number: u32,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    is_pull_request: bool,
+    events: Vec<IssueEvent>,
+    more_events: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GraphQlResponse<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct RepositoryData {
+    repository: RepositoryConnection,
+}
+
+#[derive(Deserialize)]
+struct RepositoryConnection {
+    #[serde(alias = "pullRequests")]
+    issues: IssueConnection,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IssueConnection {
+    page_info: PageInfo,
+    nodes: Vec<IssueNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    end_cursor: Option<String>,
+    has_next_page: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IssueNode {
+    number: u32,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    timeline_items: TimelineConnection,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimelineConnection {
+    page_info: PageInfo,
+    nodes: Vec<TimelineItem>,
+}
+
+#[derive(Deserialize)]
+struct RepositoryItemData {
+    repository: RepositoryItem,
+}
+
+#[derive(Deserialize)]
+struct RepositoryItem {
+    #[serde(alias = "pullRequest")]
+    issue: TimelineHolder,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimelineHolder {
+    timeline_items: TimelineConnection,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "__typename")]
+enum TimelineItem {
+    LabeledEvent {
+        #[serde(rename = "createdAt")]
+        created_at: DateTime<Utc>,
+        label: Label,
+    },
+    UnlabeledEvent {
+        #[serde(rename = "createdAt")]
+        created_at: DateTime<Utc>,
+        label: Label,
+    },
+    ClosedEvent {
+        #[serde(rename = "createdAt")]
+        created_at: DateTime<Utc>,
+    },
+    ReopenedEvent {
+        #[serde(rename = "createdAt")]
+        created_at: DateTime<Utc>,
+    },
+}
+
+impl From<TimelineItem> for IssueEvent {
+    fn from(item: TimelineItem) -> Self {
+        match item {
+            TimelineItem::LabeledEvent { created_at, label } => Self { created_at, kind: IssueEventKind::Labeled { label } },
+            TimelineItem::UnlabeledEvent { created_at, label } => Self { created_at, kind: IssueEventKind::Unlabeled { label } },
+            TimelineItem::ClosedEvent { created_at } => Self { created_at, kind: IssueEventKind::Closed },
+            TimelineItem::ReopenedEvent { created_at } => Self { created_at, kind: IssueEventKind::Reopened },
         }
     }
+}
 
-    #[must_use]
-    fn mhmw_map(&self) -> &str {
-        match &*self.name {
-            | "component: GUI"
-            | "component: gui"
-                => "component: GUI",
-            | "component: installer"
-                => "component: installer",
-            | "component: server"
-                => "component: server",
-            | "component: updater"
-                => "component: updater",
-            | "bizhawk"
-            | "frontend: BizHawk"
-            | "platform: BizHawk"
-                => "frontend: BizHawk",
-            | "frontend: EverDrive"
-            | "platform: EverDrive"
-                => "frontend: EverDrive",
-            | "frontend: Project64"
-            | "project64"
-                => "frontend: Project64",
-            | "frontend: RetroArch"
-            | "platform: RetroArch"
-                => "frontend: RetroArch",
-            | "has workaround"
-                => "has workaround",
-            | "os: Linux"
-                => "os: Linux",
-            | "os: macOS"
-                => "os: macOS",
-            | "os: Windows"
-                => "os: Windows",
-            | "status: blocked"
-                => "status: blocked",
-            | "status: duplicate"
-                => "status: duplicate",
-            | "status: good first issue"
-                => "status: good first issue",
-            | "help wanted"
-            | "status: help wanted"
-                => "status: help wanted",
-            | "status: in progress"
-                => "status: in progress",
-            | "status: invalid"
-                => "status: invalid",
-            | "status: pending release"
-                => "status: pending release",
-            | "status: question"
-                => "status: question",
-            | "status: released"
-                => "status: released",
-            | "status: wontfix"
-                => "status: wontfix",
-            | "bug"
-            | "type: bug"
-                => "type: bug",
-            | "type: documentation"
-                => "type: documentation",
-            | "enhancement"
-            | "type: enhancement"
-                => "type: enhancement",
-            | "type: maintenance"
-                => "type: maintenance",
-            _ => &self.name,
+#[must_use]
+fn list_query(connection: &str) -> String {
+    format!(r#"query($owner: String!, $name: String!, $cursor: String) {{
+  repository(owner: $owner, name: $name) {{
+    {connection}(first: 100, after: $cursor) {{
+      pageInfo {{ endCursor hasNextPage }}
+      nodes {{
+        number
+        createdAt
+        updatedAt
+        timelineItems(itemTypes: [LABELED_EVENT, UNLABELED_EVENT, CLOSED_EVENT, REOPENED_EVENT], first: {TIMELINE_PAGE_SIZE}) {{
+          pageInfo {{ endCursor hasNextPage }}
+          nodes {{ {TIMELINE_NODES} }}
+        }}
+      }}
+    }}
+  }}
+}}"#)
+}
+
+#[must_use]
+fn timeline_query(field: &str) -> String {
+    format!(r#"query($owner: String!, $name: String!, $number: Int!, $cursor: String) {{
+  repository(owner: $owner, name: $name) {{
+    {field}(number: $number) {{
+      timelineItems(itemTypes: [LABELED_EVENT, UNLABELED_EVENT, CLOSED_EVENT, REOPENED_EVENT], first: {TIMELINE_PAGE_SIZE}, after: $cursor) {{
+        pageInfo {{ endCursor hasNextPage }}
+        nodes {{ {TIMELINE_NODES} }}
+      }}
+    }}
+  }}
+}}"#)
+}
+
+/// POST a GraphQL query, reading the rate-limit headers afterwards and sleeping until reset once
+/// the quota is exhausted so long multi-repo syncs don't fail with a 403. GitHub's GraphQL POST
+/// endpoint returns no usable ETag, so there is no HTTP-level conditional request here: every
+/// listing page is downloaded in full on each run, and the per-issue `updated_at` watermark in
+/// [`collect`] only skips the follow-up timeline queries of unchanged issues.
+async fn send_graphql<T: serde::de::DeserializeOwned>(http_client: &reqwest::Client, body: &serde_json::Value) -> Result<T, Error> {
+    let response = http_client.post(GRAPHQL_ENDPOINT)
+        .json(body)
+        .send_github(true).await?;
+    let remaining = response.headers().get("x-ratelimit-remaining").and_then(|value| value.to_str().ok()).and_then(|value| value.parse::<u32>().ok());
+    let reset = response.headers().get("x-ratelimit-reset").and_then(|value| value.to_str().ok()).and_then(|value| value.parse::<i64>().ok());
+    let payload = response.json_with_text_in_error::<T>().await?;
+    if remaining == Some(0) {
+        if let Some(wait) = reset.map(|reset| reset - Utc::now().timestamp()).filter(|&wait| wait > 0) {
+            println!("{} Rate limit exhausted, sleeping {wait}s until reset", Utc::now().format("%Y-%m-%d %H:%M:%S"));
+            tokio::time::sleep(Duration::from_secs(wait as u64)).await;
         }
     }
+    Ok(payload)
+}
+
+/// Fetch every issue (or pull request, depending on `connection`) of a repository along with the
+/// first page of each one's relevant timeline items, following the outer connection's cursor.
+async fn fetch_all(http_client: &reqwest::Client, org: &str, repo: &str, connection: &str) -> Result<Vec<FetchedIssue>, Error> {
+    let query = list_query(connection);
+    let is_pull_request = connection == "pullRequests";
+    let mut cursor = None;
+    let mut all_issues = Vec::default();
+    loop {
+        let page = send_graphql::<GraphQlResponse<RepositoryData>>(http_client, &serde_json::json!({ "query": query, "variables": { "owner": org, "name": repo, "cursor": cursor } })).await?
+            .data.repository.issues;
+        for node in page.nodes {
+            let more_events = node.timeline_items.page_info.has_next_page.then(|| node.timeline_items.page_info.end_cursor).flatten();
+            all_issues.push(FetchedIssue {
+                number: node.number,
+                created_at: node.created_at,
+                updated_at: node.updated_at,
+                events: node.timeline_items.nodes.into_iter().map(IssueEvent::from).collect(),
+                is_pull_request, more_events,
+            });
+        }
+        if page.page_info.has_next_page {
+            cursor = page.page_info.end_cursor;
+        } else {
+            break
+        }
+    }
+    Ok(all_issues)
+}
+
+/// Follow the inner `timelineItems` cursor for a single issue or pull request that had more than
+/// [`TIMELINE_PAGE_SIZE`] events on its listing page.
+async fn fetch_timeline_tail(http_client: &reqwest::Client, org: &str, repo: &str, number: u32, is_pull_request: bool, mut cursor: String) -> Result<Vec<IssueEvent>, Error> {
+    let query = timeline_query(if is_pull_request { "pullRequest" } else { "issue" });
+    let mut events = Vec::default();
+    loop {
+        let connection = send_graphql::<GraphQlResponse<RepositoryItemData>>(http_client, &serde_json::json!({ "query": query, "variables": { "owner": org, "name": repo, "number": number, "cursor": cursor } })).await?
+            .data.repository.issue.timeline_items;
+        events.extend(connection.nodes.into_iter().map(IssueEvent::from));
+        match connection.page_info.has_next_page.then(|| connection.page_info.end_cursor).flatten() {
+            Some(next) => cursor = next,
+            None => break,
+        }
+    }
+    Ok(events)
+}
+
+/// Append any timeline items beyond the first page and sort the issue's events chronologically.
+async fn complete_timeline(http_client: &reqwest::Client, org: &str, repo: &str, number: u32, is_pull_request: bool, mut events: Vec<IssueEvent>, more_events: Option<String>) -> Result<Vec<IssueEvent>, Error> {
+    if let Some(cursor) = more_events {
+        events.extend(fetch_timeline_tail(http_client, org, repo, number, is_pull_request, cursor).await?);
+    }
+    events.sort_by_key(|IssueEvent { created_at, .. }| *created_at);
+    Ok(events)
 }
 
 enum Event {
@@ -310,200 +428,526 @@ enum Event {
     PullRequestUnlabeled(String),
 }
 
+/// A single label or state transition, rendered as one entry of an RSS feed.
+#[derive(Clone, Deserialize, Serialize)]
+struct FeedEntry {
+    guid: String,
+    title: String,
+    pub_date: DateTime<Utc>,
+    is_pull_request: bool,
+}
+
+impl FeedEntry {
+    #[must_use]
+    fn new(org: &str, repo: &str, number: u32, is_pull_request: bool, created_at: DateTime<Utc>, description: &str) -> Self {
+        Self {
+            guid: format!("{org}/{repo}#{number}@{}", created_at.to_rfc3339()),
+            title: format!("{repo} #{number} {description}"),
+            pub_date: created_at,
+            is_pull_request,
+        }
+    }
+}
+
+/// The result of fetching a repository: the chronological [`Event`] stream the timeline is built
+/// from, the derived [`FeedEntry`] list, and the refreshed caches to persist.
+struct Collected {
+    events: BTreeMap<DateTime<Utc>, Vec<Event>>,
+    feed: Vec<FeedEntry>,
+    last_updated: BTreeMap<u32, DateTime<Utc>>,
+    issue_events_cache: BTreeMap<u32, Vec<IssueEvent>>,
+}
+
+/// The shared secret configured on the GitHub webhook, used to verify `X-Hub-Signature-256`.
+const WEBHOOK_SECRET: &str = env!("GITHUB_WEBHOOK_SECRET");
+
+#[derive(Deserialize)]
+struct WebhookPayload {
+    action: String,
+    issue: Option<WebhookIssue>,
+    pull_request: Option<WebhookIssue>,
+    label: Option<Label>,
+    repository: WebhookRepository,
+}
+
+#[derive(Deserialize)]
+struct WebhookIssue {
+    number: u32,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct WebhookRepository {
+    name: String,
+    owner: WebhookOwner,
+}
+
+#[derive(Deserialize)]
+struct WebhookOwner {
+    login: String,
+}
+
+struct SignatureHeader(String);
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for SignatureHeader {
+    type Error = ();
+
+    async fn from_request(req: &'r rocket::Request<'_>) -> rocket::request::Outcome<Self, ()> {
+        match req.headers().get_one("X-Hub-Signature-256") {
+            Some(value) => rocket::request::Outcome::Success(Self(value.to_owned())),
+            None => rocket::request::Outcome::Error((rocket::http::Status::Unauthorized, ())),
+        }
+    }
+}
+
+struct EventHeader(String);
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for EventHeader {
+    type Error = ();
+
+    async fn from_request(req: &'r rocket::Request<'_>) -> rocket::request::Outcome<Self, ()> {
+        match req.headers().get_one("X-GitHub-Event") {
+            Some(value) => rocket::request::Outcome::Success(Self(value.to_owned())),
+            None => rocket::request::Outcome::Error((rocket::http::Status::BadRequest, ())),
+        }
+    }
+}
+
+/// Verify a `sha256=…` signature header against `HMAC-SHA256(WEBHOOK_SECRET, body)` in constant
+/// time.
+#[must_use]
+fn verify_signature(header: &str, body: &[u8]) -> bool {
+    let Some(hex_digest) = header.strip_prefix("sha256=") else { return false };
+    let Ok(expected) = hex::decode(hex_digest) else { return false };
+    let mut mac = Hmac::<Sha256>::new_from_slice(WEBHOOK_SECRET.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Replay an issue's cached events to reconstruct its contribution to the aggregate counts: whether
+/// it is currently open, and the set of canonical labels applied to it. A closed issue contributes
+/// no labels, matching the way `sync` folds the event stream.
+#[must_use]
+fn issue_contribution(events: &[IssueEvent], rules: &[CompiledRule]) -> (bool, HashSet<String>) {
+    let mut open = true;
+    let mut labels = HashSet::new();
+    for IssueEvent { kind, .. } in events {
+        match kind {
+            IssueEventKind::Labeled { label } => { labels.insert(label.map(rules)); }
+            IssueEventKind::Unlabeled { label } => { labels.remove(&label.map(rules)); }
+            IssueEventKind::Closed => open = false,
+            IssueEventKind::Reopened => open = true,
+            IssueEventKind::Other => {}
+        }
+    }
+    if open { (true, labels) } else { (false, HashSet::new()) }
+}
+
+/// Per-repository locks serializing the read-modify-write in [`apply`], since Rocket dispatches
+/// webhook deliveries concurrently and they would otherwise race on the same on-disk `Report`.
+static REPO_LOCKS: LazyLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Get (creating on first use) the lock guarding the `Report` for one repository.
+#[must_use]
+fn repo_lock(org: &str, repo: &str) -> Arc<tokio::sync::Mutex<()>> {
+    Arc::clone(REPO_LOCKS.lock().expect("repo locks poisoned").entry(format!("{org}/{repo}")).or_default())
+}
+
+/// Append a single webhook-delivered event to the cached `Report` and extend the timeline with the
+/// resulting state, without re-fetching the whole repository.
+async fn apply(org: &str, repo: &str, is_pull_request: bool, number: u32, updated_at: DateTime<Utc>, kind: IssueEventKind) -> Result<(), Error> {
+    let lock = repo_lock(org, repo);
+    let _guard = lock.lock().await;
+    let dir = Path::new("data").join(org);
+    let rules = load_label_rules(org, repo).await?;
+    let Report { last_updated, mut issue_events_cache, mut feed, mut timeline, .. } = fs::read_json(dir.join(format!("{repo}.json"))).await.missing_ok()?;
+    let mut point = timeline.last().cloned().unwrap_or_default();
+    point.day = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let description = match &kind {
+        IssueEventKind::Labeled { label } => Some(format!("gained label {}", label.map(&rules))),
+        IssueEventKind::Unlabeled { label } => Some(format!("lost label {}", label.map(&rules))),
+        IssueEventKind::Closed => Some("was closed".to_owned()),
+        IssueEventKind::Reopened => Some("was reopened".to_owned()),
+        IssueEventKind::Other => None,
+    };
+    // Fold the event into this issue's cached timeline, then re-derive its contribution to the
+    // aggregate counts the same way `sync` does and apply only the delta, so double labels, events
+    // on closed issues, and removals of absent labels can never drift the counts (or underflow).
+    let cache = issue_events_cache.entry(number).or_default();
+    let (open_before, labels_before) = issue_contribution(cache, &rules);
+    cache.push(IssueEvent { created_at: updated_at, kind });
+    cache.sort_by_key(|IssueEvent { created_at, .. }| *created_at);
+    let (open_after, labels_after) = issue_contribution(cache, &rules);
+    let (counts, open) = if is_pull_request {
+        (&mut point.pr_labels, &mut point.open_prs)
+    } else {
+        (&mut point.issue_labels, &mut point.open_issues)
+    };
+    if open_after && !open_before {
+        *open += 1;
+    } else if open_before && !open_after {
+        *open = open.saturating_sub(1);
+    }
+    for label in labels_after.difference(&labels_before) {
+        *counts.entry(label.clone()).or_default() += 1;
+    }
+    for label in labels_before.difference(&labels_after) {
+        let count = counts.entry(label.clone()).or_default();
+        *count = count.saturating_sub(1);
+    }
+    if let Some(description) = description {
+        feed.push(FeedEntry::new(org, repo, number, is_pull_request, updated_at, &description));
+    }
+    // Deliberately leave `last_updated` untouched: the webhook payload carries the issue's
+    // `updated_at`, not the event's own timestamp, so the appended event uses a best-effort stamp.
+    // Not advancing the watermark means the next `sync` re-fetches this issue's real timeline and
+    // overwrites the cached entry, reconciling any approximation.
+    let labels = point.issue_labels.keys().chain(point.pr_labels.keys()).cloned().collect();
+    timeline.push(point);
+    fs::create_dir_all(&dir).await?;
+    fs::write_json(dir.join(format!("{repo}.json")), Report { labels, last_updated, issue_events_cache, feed, timeline }).await?;
+    Ok(())
+}
+
+/// Dispatch an accepted delivery on its `X-GitHub-Event`, applying label and state changes and
+/// ignoring everything else.
+async fn handle_delivery(event: &str, body: &[u8]) -> Result<(), Error> {
+    let is_pull_request = match event {
+        "issues" => false,
+        "pull_request" => true,
+        // Ignore everything else (including the `ping` sent when the hook is created, whose payload
+        // has no `action`) before parsing the issue/PR-shaped payload, so it isn't a 500.
+        _ => return Ok(()),
+    };
+    let payload = serde_json::from_slice::<WebhookPayload>(body)?;
+    let item = if is_pull_request { payload.pull_request } else { payload.issue };
+    let Some(WebhookIssue { number, updated_at }) = item else { return Ok(()) };
+    let kind = match &*payload.action {
+        "labeled" => match payload.label {
+            Some(label) => IssueEventKind::Labeled { label },
+            None => return Ok(()),
+        },
+        "unlabeled" => match payload.label {
+            Some(label) => IssueEventKind::Unlabeled { label },
+            None => return Ok(()),
+        },
+        "closed" => IssueEventKind::Closed,
+        "reopened" => IssueEventKind::Reopened,
+        _ => return Ok(()),
+    };
+    apply(&payload.repository.owner.login, &payload.repository.name, is_pull_request, number, updated_at, kind).await
+}
+
+#[rocket::post("/", data = "<data>")]
+async fn webhook(signature: SignatureHeader, event: EventHeader, data: rocket::data::Data<'_>) -> rocket::http::Status {
+    let body = match data.open(2.mebibytes()).into_bytes().await {
+        Ok(body) if body.is_complete() => body.into_inner(),
+        _ => return rocket::http::Status::PayloadTooLarge,
+    };
+    if !verify_signature(&signature.0, &body) {
+        return rocket::http::Status::Unauthorized
+    }
+    match handle_delivery(&event.0, &body).await {
+        Ok(()) => rocket::http::Status::NoContent,
+        Err(e) => {
+            eprintln!("{} webhook delivery failed: {e}", Utc::now().format("%Y-%m-%d %H:%M:%S"));
+            rocket::http::Status::InternalServerError
+        }
+    }
+}
+
 #[derive(clap::Parser)]
 struct Args {
-    repos: Vec<Repo>,
+    #[clap(subcommand)]
+    subcommand: Subcommand,
+}
+
+#[derive(clap::Subcommand)]
+enum Subcommand {
+    /// Refresh the `Report` timeline for each repository in one batch run.
+    Sync {
+        repos: Vec<Repo>,
+    },
+    /// Print an RSS feed of issue label and state transitions.
+    EmitIssues {
+        repo: Repo,
+        /// Only include entries newer than now minus this window, e.g. `30d` or `12h`.
+        #[clap(long, value_parser = parse_duration)]
+        max_age: Option<chrono::TimeDelta>,
+    },
+    /// Print an RSS feed of pull request label and state transitions.
+    EmitPrs {
+        repo: Repo,
+        /// Only include entries newer than now minus this window, e.g. `30d` or `12h`.
+        #[clap(long, value_parser = parse_duration)]
+        max_age: Option<chrono::TimeDelta>,
+    },
+    /// Run a webhook server that keeps the `Report`s current as deliveries arrive.
+    Serve {
+        #[clap(long, default_value_t = 8080)]
+        port: u16,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+enum DurationParseError {
+    #[error("empty duration")]
+    Empty,
+    #[error("duration must end in one of s, m, h, d, w")]
+    Unit,
+    #[error(transparent)] ParseInt(#[from] std::num::ParseIntError),
+}
+
+fn parse_duration(s: &str) -> Result<chrono::TimeDelta, DurationParseError> {
+    let (offset, _) = s.char_indices().next_back().ok_or(DurationParseError::Empty)?;
+    let (value, unit) = s.split_at(offset);
+    let value = value.parse::<i64>()?;
+    Ok(match unit {
+        "s" => chrono::TimeDelta::seconds(value),
+        "m" => chrono::TimeDelta::minutes(value),
+        "h" => chrono::TimeDelta::hours(value),
+        "d" => chrono::TimeDelta::days(value),
+        "w" => chrono::TimeDelta::weeks(value),
+        _ => return Err(DurationParseError::Unit),
+    })
 }
 
 #[derive(Debug, thiserror::Error)]
 enum Error {
-    #[error(transparent)] HeaderToStr(#[from] reqwest::header::ToStrError),
     #[error(transparent)] InvalidHeaderValue(#[from] reqwest::header::InvalidHeaderValue),
+    #[error(transparent)] Json(#[from] serde_json::Error),
+    #[error(transparent)] Regex(#[from] regex::Error),
     #[error(transparent)] Reqwest(#[from] reqwest::Error),
+    #[error(transparent)] Rocket(#[from] rocket::Error),
     #[error(transparent)] Wheel(#[from] wheel::Error),
     #[error("attempted to remove a label that wasn't present")]
     RemovedNonexistentLabel {
-        events_url: Url,
+        number: u32,
         label: String,
     },
 }
 
-#[wheel::main]
-async fn main(Args { repos }: Args) -> Result<(), Error> {
-    let mut headers = reqwest::header::HeaderMap::new();
-    headers.insert(reqwest::header::AUTHORIZATION, reqwest::header::HeaderValue::from_str(concat!("token ", env!("GITHUB_TOKEN")))?);
-    let http_client = reqwest::Client::builder()
-        .user_agent(concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"), " (", env!("CARGO_PKG_REPOSITORY"), ")"))
-        .default_headers(headers)
-        .timeout(Duration::from_secs(600))
-        .http2_prior_knowledge()
-        .use_rustls_tls()
-        .https_only(true)
-        .build()?;
-    for Repo { org, repo } in repos {
-        let dir = Path::new("data").join(&org);
-        let Report { mut last_updated, mut issue_events_cache, .. } = fs::read_json(dir.join(format!("{repo}.json"))).await.missing_ok()?;
-        let mut events = BTreeMap::<_, Vec<_>>::default();
-        let mut all_issues = Vec::default();
-        println!("{} Checking {org}/{repo}", Utc::now().format("%Y-%m-%d %H:%M:%S"));
-        let mut response = http_client.get(&format!("https://api.github.com/repos/{org}/{repo}/issues"))
-            .query(&[
-                ("state", "all"),
-            ])
-            .send_github(true).await?;
-        loop {
-            if_chain! {
-                if let Some(links) = response.headers().get(reqwest::header::LINK);
-                if let Ok((_, next)) = links.to_str()?
-                    .split(", ")
-                    .filter_map(|link| regex_captures!("^<(.+)>; rel=\"next\"$", link))
-                    .exactly_one();
-                then {
-                    let next = next.to_owned();
-                    all_issues.extend(response.json_with_text_in_error::<Vec<Issue>>().await?);
-                    println!("{} Checking next {org}/{repo} page: {next}", Utc::now().format("%Y-%m-%d %H:%M:%S"));
-                    response = http_client.get(next)
-                        .send_github(true).await?;
-                } else {
-                    all_issues.extend(response.json_with_text_in_error::<Vec<Issue>>().await?);
-                    break
-                }
-            }
-        }
-        for Issue { number, created_at, updated_at, pull_request, events_url } in all_issues {
-            events.entry(created_at).or_default().push(if pull_request.is_some() {
-                Event::PullRequestOpened(HashSet::default())
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Render the given entries (already filtered to one kind and sorted newest first) as an RSS 2.0
+/// document.
+#[must_use]
+fn render_feed(org: &str, repo: &str, is_pull_request: bool, entries: &[FeedEntry]) -> String {
+    let kind = if is_pull_request { "pull requests" } else { "issues" };
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n<channel>\n");
+    out.push_str(&format!("<title>{}</title>\n", xml_escape(&format!("{org}/{repo} {kind}"))));
+    out.push_str(&format!("<link>https://github.com/{org}/{repo}</link>\n"));
+    out.push_str(&format!("<description>{}</description>\n", xml_escape(&format!("Label and state transitions for {org}/{repo} {kind}"))));
+    for FeedEntry { guid, title, pub_date, .. } in entries {
+        out.push_str("<item>\n");
+        out.push_str(&format!("<title>{}</title>\n", xml_escape(title)));
+        out.push_str(&format!("<guid isPermaLink=\"false\">{}</guid>\n", xml_escape(guid)));
+        out.push_str(&format!("<pubDate>{}</pubDate>\n", pub_date.to_rfc2822()));
+        out.push_str("</item>\n");
+    }
+    out.push_str("</channel>\n</rss>\n");
+    out
+}
+
+/// Fetch a repository and fold its issues and pull requests into the [`Event`] stream and the feed
+/// entries derived from it, refreshing the persisted caches along the way.
+async fn collect(http_client: &reqwest::Client, org: &str, repo: &str, rules: &[CompiledRule], mut last_updated: BTreeMap<u32, DateTime<Utc>>, mut issue_events_cache: BTreeMap<u32, Vec<IssueEvent>>) -> Result<Collected, Error> {
+    let mut events = BTreeMap::<_, Vec<_>>::default();
+    let mut feed = Vec::default();
+    println!("{} Checking {org}/{repo}", Utc::now().format("%Y-%m-%d %H:%M:%S"));
+    let mut all_issues = fetch_all(http_client, org, repo, "issues").await?;
+    all_issues.extend(fetch_all(http_client, org, repo, "pullRequests").await?);
+    for FetchedIssue { number, created_at, updated_at, is_pull_request, events: inline_events, more_events } in all_issues {
+        events.entry(created_at).or_default().push(if is_pull_request {
+            Event::PullRequestOpened(HashSet::default())
+        } else {
+            Event::IssueOpened(HashSet::default())
+        });
+        feed.push(FeedEntry::new(org, repo, number, is_pull_request, created_at, "was opened"));
+        let mut labels = HashSet::new();
+        println!("{} Checking {org}/{repo} issue: #{number}", Utc::now().format("%Y-%m-%d %H:%M:%S"));
+        // When the listing reports the same `updated_at` we recorded last run, the cached events
+        // are still current, so reuse them and skip the timeline-tail fetch entirely. The listing
+        // page itself, including each issue's first page of timeline items, has already been
+        // downloaded in full either way; no HTTP-level conditional request is made.
+        let issue_events = match issue_events_cache.entry(number) {
+            btree_map::Entry::Occupied(mut entry) => if last_updated.get(&number).is_some_and(|&last_updated| last_updated == updated_at) {
+                entry.into_mut()
             } else {
-                Event::IssueOpened(HashSet::default())
-            });
-            let mut labels = HashSet::new();
-            println!("{} Checking {org}/{repo} issue: {events_url}", Utc::now().format("%Y-%m-%d %H:%M:%S"));
-            let issue_events = match issue_events_cache.entry(number) {
-                btree_map::Entry::Occupied(mut entry) => if last_updated.get(&number).is_some_and(|&last_updated| last_updated == updated_at) {
-                    entry.into_mut()
-                } else {
-                    let mut issue_events = http_client.get(events_url.clone())
-                        .send_github(true).await?
-                        .json_with_text_in_error::<Vec<IssueEvent>>().await?;
-                    issue_events.sort_by_key(|IssueEvent { created_at, .. }| *created_at);
-                    *entry.get_mut() = issue_events;
-                    entry.into_mut()
-                },
-                btree_map::Entry::Vacant(entry) => {
-                    let mut issue_events = http_client.get(events_url.clone())
-                        .send_github(true).await?
-                        .json_with_text_in_error::<Vec<IssueEvent>>().await?;
-                    issue_events.sort_by_key(|IssueEvent { created_at, .. }| *created_at);
-                    entry.insert(issue_events)
-                }
-            };
-            let mut open = true;
-            for IssueEvent { created_at, kind } in issue_events {
-                match kind {
-                    IssueEventKind::Labeled { label } => {
-                        if labels.insert(label.map(&org, &repo).to_owned()) && open {
-                            events.entry(*created_at).or_default().push(if pull_request.is_some() {
-                                Event::PullRequestLabeled(label.map(&org, &repo).to_owned())
-                            } else {
-                                Event::IssueLabeled(label.map(&org, &repo).to_owned())
-                            });
-                        }
-                    }
-                    IssueEventKind::Unlabeled { label } => {
-                        if !labels.remove(label.map(&org, &repo)) {
-                            return Err(Error::RemovedNonexistentLabel { events_url, label: label.map(&org, &repo).to_owned() })
-                        }
-                        if open {
-                            events.entry(*created_at).or_default().push(if pull_request.is_some() {
-                                Event::PullRequestUnlabeled(label.map(&org, &repo).to_owned())
-                            } else {
-                                Event::IssueUnlabeled(label.map(&org, &repo).to_owned())
-                            });
-                        }
-                    }
-                    IssueEventKind::Closed => {
-                        open = false;
-                        events.entry(*created_at).or_default().push(if pull_request.is_some() {
-                            Event::PullRequestClosed(labels.clone())
+                *entry.get_mut() = complete_timeline(http_client, org, repo, number, is_pull_request, inline_events, more_events).await?;
+                entry.into_mut()
+            },
+            btree_map::Entry::Vacant(entry) => entry.insert(complete_timeline(http_client, org, repo, number, is_pull_request, inline_events, more_events).await?),
+        };
+        let mut open = true;
+        for IssueEvent { created_at, kind } in issue_events {
+            match kind {
+                IssueEventKind::Labeled { label } => {
+                    let canonical = label.map(rules);
+                    if labels.insert(canonical.clone()) && open {
+                        events.entry(*created_at).or_default().push(if is_pull_request {
+                            Event::PullRequestLabeled(canonical.clone())
                         } else {
-                            Event::IssueClosed(labels.clone())
+                            Event::IssueLabeled(canonical.clone())
                         });
+                        feed.push(FeedEntry::new(org, repo, number, is_pull_request, *created_at, &format!("gained label {canonical}")));
+                    }
+                }
+                IssueEventKind::Unlabeled { label } => {
+                    let canonical = label.map(rules);
+                    if !labels.remove(&canonical) {
+                        return Err(Error::RemovedNonexistentLabel { number, label: canonical })
                     }
-                    IssueEventKind::Reopened => {
-                        open = true;
-                        events.entry(*created_at).or_default().push(if pull_request.is_some() {
-                            Event::PullRequestOpened(labels.clone())
+                    if open {
+                        events.entry(*created_at).or_default().push(if is_pull_request {
+                            Event::PullRequestUnlabeled(canonical.clone())
                         } else {
-                            Event::IssueOpened(labels.clone())
+                            Event::IssueUnlabeled(canonical.clone())
                         });
+                        feed.push(FeedEntry::new(org, repo, number, is_pull_request, *created_at, &format!("lost label {canonical}")));
                     }
-                    IssueEventKind::Other => {}
                 }
+                IssueEventKind::Closed => {
+                    open = false;
+                    events.entry(*created_at).or_default().push(if is_pull_request {
+                        Event::PullRequestClosed(labels.clone())
+                    } else {
+                        Event::IssueClosed(labels.clone())
+                    });
+                    feed.push(FeedEntry::new(org, repo, number, is_pull_request, *created_at, "was closed"));
+                }
+                IssueEventKind::Reopened => {
+                    open = true;
+                    events.entry(*created_at).or_default().push(if is_pull_request {
+                        Event::PullRequestOpened(labels.clone())
+                    } else {
+                        Event::IssueOpened(labels.clone())
+                    });
+                    feed.push(FeedEntry::new(org, repo, number, is_pull_request, *created_at, "was reopened"));
+                }
+                IssueEventKind::Other => {}
             }
-            last_updated.insert(number, updated_at);
         }
-        let mut timeline = Vec::with_capacity(events.len());
-        let mut open_issues = 0;
-        let mut open_prs = 0;
-        let mut issue_labels = BTreeMap::default();
-        let mut pr_labels = BTreeMap::default();
-        for (timestamp, events) in events {
-            timeline.push(DataPoint {
-                day: timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
-                issue_labels: issue_labels.clone(),
-                pr_labels: pr_labels.clone(),
-                open_issues, open_prs,
-            });
-            for event in events {
-                match event {
-                    Event::IssueOpened(labels) => {
-                        open_issues += 1;
-                        for label in labels {
-                            *issue_labels.entry(label).or_default() += 1;
-                        }
+        last_updated.insert(number, updated_at);
+    }
+    Ok(Collected { events, feed, last_updated, issue_events_cache })
+}
+
+/// Refresh and persist the `Report` timeline for one repository.
+async fn sync(http_client: &reqwest::Client, org: &str, repo: &str) -> Result<(), Error> {
+    let dir = Path::new("data").join(org);
+    let rules = load_label_rules(org, repo).await?;
+    let Report { last_updated, issue_events_cache, .. } = fs::read_json(dir.join(format!("{repo}.json"))).await.missing_ok()?;
+    let Collected { events, feed, last_updated, issue_events_cache } = collect(http_client, org, repo, &rules, last_updated, issue_events_cache).await?;
+    let mut timeline = Vec::with_capacity(events.len());
+    let mut open_issues = 0;
+    let mut open_prs = 0;
+    let mut issue_labels = BTreeMap::default();
+    let mut pr_labels = BTreeMap::default();
+    for (timestamp, events) in events {
+        timeline.push(DataPoint {
+            day: timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+            issue_labels: issue_labels.clone(),
+            pr_labels: pr_labels.clone(),
+            open_issues, open_prs,
+        });
+        for event in events {
+            match event {
+                Event::IssueOpened(labels) => {
+                    open_issues += 1;
+                    for label in labels {
+                        *issue_labels.entry(label).or_default() += 1;
                     }
-                    Event::IssueClosed(labels) => {
-                        open_issues -= 1;
-                        for label in labels {
-                            *issue_labels.entry(label).or_default() -= 1;
-                        }
+                }
+                Event::IssueClosed(labels) => {
+                    open_issues -= 1;
+                    for label in labels {
+                        *issue_labels.entry(label).or_default() -= 1;
                     }
-                    Event::PullRequestOpened(labels) => {
-                        open_prs += 1;
-                        for label in labels {
-                            *pr_labels.entry(label).or_default() += 1;
-                        }
+                }
+                Event::PullRequestOpened(labels) => {
+                    open_prs += 1;
+                    for label in labels {
+                        *pr_labels.entry(label).or_default() += 1;
                     }
-                    Event::PullRequestClosed(labels) => {
-                        open_prs -= 1;
-                        for label in labels {
-                            *pr_labels.entry(label).or_default() -= 1;
-                        }
+                }
+                Event::PullRequestClosed(labels) => {
+                    open_prs -= 1;
+                    for label in labels {
+                        *pr_labels.entry(label).or_default() -= 1;
                     }
-                    Event::IssueLabeled(label) => *issue_labels.entry(label).or_default() += 1,
-                    Event::IssueUnlabeled(label) => *issue_labels.entry(label).or_default() -= 1,
-                    Event::PullRequestLabeled(label) => *pr_labels.entry(label).or_default() += 1,
-                    Event::PullRequestUnlabeled(label) => *pr_labels.entry(label).or_default() -= 1,
                 }
+                Event::IssueLabeled(label) => *issue_labels.entry(label).or_default() += 1,
+                Event::IssueUnlabeled(label) => *issue_labels.entry(label).or_default() -= 1,
+                Event::PullRequestLabeled(label) => *pr_labels.entry(label).or_default() += 1,
+                Event::PullRequestUnlabeled(label) => *pr_labels.entry(label).or_default() -= 1,
             }
-            timeline.push(DataPoint {
-                day: timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
-                issue_labels: issue_labels.clone(),
-                pr_labels: pr_labels.clone(),
-                open_issues, open_prs,
-            });
         }
         timeline.push(DataPoint {
-            day: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            day: timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
             issue_labels: issue_labels.clone(),
             pr_labels: pr_labels.clone(),
             open_issues, open_prs,
         });
-        fs::create_dir_all(&dir).await?;
-        fs::write_json(dir.join(format!("{repo}.json")), Report {
-            labels: issue_labels.into_keys().chain(pr_labels.into_keys()).collect(),
-            last_updated, issue_events_cache, timeline,
-        }).await?;
+    }
+    timeline.push(DataPoint {
+        day: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        issue_labels: issue_labels.clone(),
+        pr_labels: pr_labels.clone(),
+        open_issues, open_prs,
+    });
+    fs::create_dir_all(&dir).await?;
+    fs::write_json(dir.join(format!("{repo}.json")), Report {
+        labels: issue_labels.into_keys().chain(pr_labels.into_keys()).collect(),
+        last_updated, issue_events_cache, feed, timeline,
+    }).await?;
+    Ok(())
+}
+
+/// Print the persisted feed for one repository to stdout, keeping only entries of the requested
+/// kind that fall within `max_age` of now. Reads the cached `Report` written by `sync`/`serve`
+/// rather than crawling the API on every poll.
+async fn emit(org: &str, repo: &str, is_pull_request: bool, max_age: Option<chrono::TimeDelta>) -> Result<(), Error> {
+    let dir = Path::new("data").join(org);
+    let Report { feed, .. } = fs::read_json(dir.join(format!("{repo}.json"))).await.missing_ok()?;
+    let cutoff = max_age.map(|max_age| Utc::now() - max_age);
+    let mut feed = feed.into_iter()
+        .filter(|entry| entry.is_pull_request == is_pull_request)
+        .filter(|entry| cutoff.is_none_or(|cutoff| entry.pub_date >= cutoff))
+        .collect::<Vec<_>>();
+    feed.sort_by_key(|entry| std::cmp::Reverse(entry.pub_date));
+    print!("{}", render_feed(org, repo, is_pull_request, &feed));
+    Ok(())
+}
+
+#[wheel::main]
+async fn main(Args { subcommand }: Args) -> Result<(), Error> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(reqwest::header::AUTHORIZATION, reqwest::header::HeaderValue::from_str(concat!("token ", env!("GITHUB_TOKEN")))?);
+    let http_client = reqwest::Client::builder()
+        .user_agent(concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"), " (", env!("CARGO_PKG_REPOSITORY"), ")"))
+        .default_headers(headers)
+        .timeout(Duration::from_secs(600))
+        .http2_prior_knowledge()
+        .use_rustls_tls()
+        .https_only(true)
+        .build()?;
+    match subcommand {
+        Subcommand::Sync { repos } => for Repo { org, repo } in repos {
+            sync(&http_client, &org, &repo).await?;
+        },
+        Subcommand::EmitIssues { repo: Repo { org, repo }, max_age } => emit(&org, &repo, false, max_age).await?,
+        Subcommand::EmitPrs { repo: Repo { org, repo }, max_age } => emit(&org, &repo, true, max_age).await?,
+        Subcommand::Serve { port } => {
+            let config = rocket::Config { port, ..rocket::Config::default() };
+            let _ = rocket::custom(config)
+                .mount("/", rocket::routes![webhook])
+                .launch().await?;
+        }
     }
     Ok(())
 }